@@ -4,6 +4,8 @@ use spl_token::amount_to_ui_amount;
 use clap::Parser;
 use solana_sdk::{signature::Keypair, signer::Signer};
 use colored::*;
+use serde::Serialize;
+use indicatif::{ProgressBar, ProgressStyle};
 
 use crate::balance;
 
@@ -15,17 +17,189 @@ pub struct ClaimArgs {
         help = "Amount of ore to claim."
     )]
     pub amount: Option<f64>,
+
+    #[arg(
+        long,
+        help = "Wait for the claim cooldown to expire and automatically retry until the claim succeeds."
+    )]
+    pub wait: bool,
+
+    #[arg(
+        long,
+        help = "Skip the interactive Y/n confirmation prompt."
+    )]
+    pub yes: bool,
+
+    #[arg(
+        long,
+        help = "Suppress human-readable output and print a single JSON result object to stdout instead."
+    )]
+    pub json: bool,
+
+    #[arg(
+        long,
+        help = "Claim the entire available balance instead of prompting for an amount."
+    )]
+    pub all: bool,
+
+    #[arg(
+        long,
+        value_name = "COUNT",
+        default_value_t = 5,
+        help = "Number of times to retry the claim request after a transport error before giving up."
+    )]
+    pub retry_attempts: u32,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5,
+        help = "Base delay for exponential backoff between retries after a transport error."
+    )]
+    pub retry_base_delay: u64,
 }
 
-pub async fn claim(args: ClaimArgs, key: Keypair, url: String, unsecure: bool) {
-    // Check balance before proceeding to claim
-    let client = reqwest::Client::new();
-    let url_prefix = if unsecure {
-        "http".to_string()
+// Small buffer added on top of the server-reported cooldown so we don't race the on-chain clock.
+const WAIT_RETRY_BUFFER_SECS: u64 = 2;
+
+// Upper bound on the exponential backoff delay between retries, regardless of attempt count.
+const RETRY_MAX_DELAY_SECS: u64 = 60;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ClaimStatus {
+    Success,
+    Cooldown,
+    Insufficient,
+    Cancelled,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct ClaimOutcome {
+    requested_grains: u64,
+    available_grains: u64,
+    status: ClaimStatus,
+    seconds_until_next_claim: Option<u64>,
+}
+
+/// Abstracts the amount prompt and the Y/n confirmation so `claim` can run without ever
+/// touching stdin in scriptable (`--json`/`--yes`) contexts.
+trait ClaimPrompter {
+    fn prompt_amount(&mut self) -> Option<f64>;
+    fn confirm(&mut self, amount_ui: f64) -> bool;
+}
+
+struct InteractivePrompter;
+
+impl ClaimPrompter for InteractivePrompter {
+    fn prompt_amount(&mut self) -> Option<f64> {
+        print!("Enter the amount to claim: ");
+        io::stdout().flush().unwrap();
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        input.trim().parse::<f64>().ok()
+    }
+
+    fn confirm(&mut self, amount_ui: f64) -> bool {
+        println!(
+            "{}",
+            format!("Are you sure you want to claim {} ORE? (Y/n)", amount_ui).red()
+        );
+        io::stdout().flush().unwrap();
+
+        let mut confirm = String::new();
+        io::stdin().read_line(&mut confirm).unwrap();
+
+        let confirm = confirm.trim().to_lowercase();
+        confirm == "y" || confirm.is_empty() || confirm == "yes"
+    }
+}
+
+/// Never reads from stdin: the amount must already be known and confirmation is always granted
+/// (this is what `--yes`/`--json` opt into).
+struct NonInteractivePrompter;
+
+impl ClaimPrompter for NonInteractivePrompter {
+    fn prompt_amount(&mut self) -> Option<f64> {
+        None
+    }
+
+    fn confirm(&mut self, _amount_ui: f64) -> bool {
+        true
+    }
+}
+
+/// Starts a ticking spinner with `message`, or `None` when `quiet` (e.g. `--json`) so
+/// machine-readable output is never interleaved with spinner frames.
+fn start_spinner(quiet: bool, message: &str) -> Option<ProgressBar> {
+    if quiet {
+        return None;
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("{spinner:.green} {msg}")
+            .unwrap(),
+    );
+    pb.set_message(message.to_string());
+    pb.enable_steady_tick(Duration::from_millis(100));
+    Some(pb)
+}
+
+/// Clears the spinner (if any) and prints a final ✓/✗ message in its place.
+fn finish_spinner(pb: Option<ProgressBar>, ok: bool, message: &str) {
+    if let Some(pb) = pb {
+        let prefix = if ok { "✓".green() } else { "✗".red() };
+        pb.finish_with_message(format!("{} {}", prefix, message));
+    }
+}
+
+/// The meaningful shapes a `/claim` response body can take.
+#[derive(Debug, PartialEq)]
+enum ClaimResponse {
+    Success,
+    /// Claim rejected because the cooldown hasn't elapsed yet; holds seconds remaining.
+    Cooldown(u64),
+    /// Anything that isn't `SUCCESS` or a cooldown-seconds value in `0..=1800`.
+    Unexpected,
+}
+
+/// Parses the raw `/claim` response body into a [`ClaimResponse`]. Pulled out as a pure
+/// function so the status mapping (in particular the cooldown-seconds boundary) can be
+/// unit tested without a live server.
+fn parse_claim_response(response_text: &str) -> ClaimResponse {
+    if response_text == "SUCCESS" {
+        ClaimResponse::Success
+    } else if let Ok(time) = response_text.parse::<u64>() {
+        if time > 1800 {
+            ClaimResponse::Unexpected
+        } else {
+            ClaimResponse::Cooldown(1800 - time)
+        }
     } else {
-        "https".to_string()
-    };
+        ClaimResponse::Unexpected
+    }
+}
 
+/// Computes the exponential backoff delay for the given retry attempt (0-indexed), capped at
+/// `RETRY_MAX_DELAY_SECS` and immune to shift overflow regardless of `attempt`.
+fn backoff_delay_secs(base_delay: u64, attempt: u32) -> u64 {
+    base_delay
+        .saturating_mul(1u64 << attempt.min(63))
+        .min(RETRY_MAX_DELAY_SECS)
+}
+
+/// Fetches the claimable balance (in grains) from `/miner/rewards`, or `None` if the request
+/// fails or the response body is not a number. A zero balance is returned as `Some(0)`.
+async fn fetch_balance_grains(
+    client: &reqwest::Client,
+    url_prefix: &str,
+    url: &str,
+    key: &Keypair,
+) -> Option<u64> {
     let balance_resp = client
         .get(format!(
             "{}://{}/miner/rewards?pubkey={}",
@@ -36,143 +210,404 @@ pub async fn claim(args: ClaimArgs, key: Keypair, url: String, unsecure: bool) {
         .send()
         .await;
 
-    let balance_grains = match balance_resp {
+    match balance_resp {
         Ok(resp) => {
             let balance = resp.text().await.unwrap_or("0".to_string());
             match balance.parse::<f64>() {
-                Ok(parsed_balance) => (parsed_balance * 10f64.powf(ore_api::consts::TOKEN_DECIMALS as f64)) as u64,
-                Err(_) => {
-                    println!("\nThere is no balance to claim.");
-                    prompt_to_continue(); // Pause before returning
-                    return;
+                Ok(parsed_balance) => {
+                    Some((parsed_balance * 10f64.powf(ore_api::consts::TOKEN_DECIMALS as f64)) as u64)
                 }
+                Err(_) => None,
             }
         }
-        Err(_) => {
-            println!("\nThere is no balance to claim.");
-            prompt_to_continue(); // Pause before returning
-            return;
+        Err(_) => None,
+    }
+}
+
+/// Prints the outcome as a JSON object when `json` is set (otherwise nothing is printed here),
+/// then exits the process with a non-zero code on anything other than success. Never blocks on stdin when `non_interactive` (`--json`/`--yes`)
+/// is set.
+fn finish(json: bool, non_interactive: bool, outcome: ClaimOutcome) -> ! {
+    let failed = !matches!(outcome.status, ClaimStatus::Success);
+    if json {
+        println!("{}", serde_json::to_string(&outcome).unwrap());
+    } else if !non_interactive {
+        prompt_to_continue();
+    }
+    std::process::exit(if failed { 1 } else { 0 });
+}
+
+pub async fn claim(args: ClaimArgs, key: Keypair, url: String, unsecure: bool) {
+    let non_interactive = args.json || args.yes;
+    let mut prompter: Box<dyn ClaimPrompter> = if non_interactive {
+        Box::new(NonInteractivePrompter)
+    } else {
+        Box::new(InteractivePrompter)
+    };
+
+    // Check balance before proceeding to claim
+    let client = reqwest::Client::new();
+    let url_prefix = if unsecure {
+        "http".to_string()
+    } else {
+        "https".to_string()
+    };
+
+    let rewards_spinner = start_spinner(args.json, "Checking rewards...");
+    let balance_result = fetch_balance_grains(&client, &url_prefix, &url, &key).await;
+    finish_spinner(rewards_spinner, balance_result.is_some(), "Rewards checked");
+
+    let balance_grains = match balance_result {
+        Some(balance_grains) => balance_grains,
+        None => {
+            if !args.json {
+                println!("\nThere is no balance to claim.");
+            }
+            finish(
+                args.json,
+                non_interactive,
+                ClaimOutcome {
+                    requested_grains: 0,
+                    available_grains: 0,
+                    status: ClaimStatus::Insufficient,
+                    seconds_until_next_claim: None,
+                },
+            );
         }
     };
 
     // If balance is zero, inform the user and return to keypair selection
     if balance_grains == 0 {
-        println!("\nThere is no balance to claim.");
-        prompt_to_continue(); // Pause before returning
-        return;
+        if !args.json {
+            println!("\nThere is no balance to claim.");
+        }
+        finish(
+            args.json,
+            non_interactive,
+            ClaimOutcome {
+                requested_grains: 0,
+                available_grains: 0,
+                status: ClaimStatus::Insufficient,
+                seconds_until_next_claim: None,
+            },
+        );
     }
 
     // Display balance after confirming the user has rewards
-    balance(&key, url.clone(), unsecure).await;
+    if !args.json {
+        balance(&key, url.clone(), unsecure).await;
+    }
 
-    // Prompt for amount if not provided
-    let claim_amount = if let Some(amount) = args.amount {
-        amount
+    // Claiming the whole balance bypasses the amount prompt entirely.
+    let mut claim_amount_grains = if args.all {
+        balance_grains
     } else {
-        print!("Enter the amount to claim: ");
-        io::stdout().flush().unwrap();
-
-        let mut input = String::new();
-        io::stdin().read_line(&mut input).unwrap();
-
-        match input.trim().parse::<f64>() {
-            Ok(val) => val,
-            Err(_) => {
-                println!("Please enter a valid number.");
-                prompt_to_continue(); // Pause before returning
-                return;
+        // Prompt for amount if not provided
+        let claim_amount = if let Some(amount) = args.amount {
+            amount
+        } else {
+            match prompter.prompt_amount() {
+                Some(val) => val,
+                None => {
+                    if !args.json {
+                        println!("Please enter a valid number.");
+                    }
+                    finish(
+                        args.json,
+                        non_interactive,
+                        ClaimOutcome {
+                            requested_grains: 0,
+                            available_grains: balance_grains,
+                            status: ClaimStatus::Cancelled,
+                            seconds_until_next_claim: None,
+                        },
+                    );
+                }
             }
-        }
-    };
+        };
 
-    // Convert the claim amount to the smallest unit
-    let claim_amount_grains = (claim_amount * 10f64.powf(ore_api::consts::TOKEN_DECIMALS as f64)) as u64;
+        // Convert the claim amount to the smallest unit
+        (claim_amount * 10f64.powf(ore_api::consts::TOKEN_DECIMALS as f64)) as u64
+    };
 
     // Handle the case where the claim amount is zero
     if claim_amount_grains == 0 {
-        println!("You entered 0 rewards to claim, so no claim will be made.");
-        prompt_to_continue(); // Pause before returning
-        return;
+        if !args.json {
+            println!("You entered 0 rewards to claim, so no claim will be made.");
+        }
+        finish(
+            args.json,
+            non_interactive,
+            ClaimOutcome {
+                requested_grains: 0,
+                available_grains: balance_grains,
+                status: ClaimStatus::Cancelled,
+                seconds_until_next_claim: None,
+            },
+        );
     }
 
     // Ensure the claim amount does not exceed the available balance
     if claim_amount_grains > balance_grains {
-        println!(
-            "You do not have enough rewards to claim {} ORE.",
-            amount_to_ui_amount(claim_amount_grains, ore_api::consts::TOKEN_DECIMALS)
+        if !args.json {
+            println!(
+                "You do not have enough rewards to claim {} ORE.",
+                amount_to_ui_amount(claim_amount_grains, ore_api::consts::TOKEN_DECIMALS)
+            );
+            println!(
+                "Please enter an amount less than or equal to {} ORE.",
+                amount_to_ui_amount(balance_grains, ore_api::consts::TOKEN_DECIMALS)
+            );
+        }
+        finish(
+            args.json,
+            non_interactive,
+            ClaimOutcome {
+                requested_grains: claim_amount_grains,
+                available_grains: balance_grains,
+                status: ClaimStatus::Insufficient,
+                seconds_until_next_claim: None,
+            },
         );
-        println!(
-            "Please enter an amount less than or equal to {} ORE.",
-            amount_to_ui_amount(balance_grains, ore_api::consts::TOKEN_DECIMALS)
+    }
+
+    // Ask for confirmation
+    if !prompter.confirm(amount_to_ui_amount(claim_amount_grains, ore_api::consts::TOKEN_DECIMALS)) {
+        if !args.json {
+            println!("Claim cancelled.");
+        }
+        finish(
+            args.json,
+            non_interactive,
+            ClaimOutcome {
+                requested_grains: claim_amount_grains,
+                available_grains: balance_grains,
+                status: ClaimStatus::Cancelled,
+                seconds_until_next_claim: None,
+            },
         );
-        prompt_to_continue(); // Pause before returning
-        return;
-    }
-
-    // Ask for confirmation with red colored text
-    println!(
-        "{}",
-        format!(
-            "Are you sure you want to claim {} ORE? (Y/n)",
-            amount_to_ui_amount(claim_amount_grains, ore_api::consts::TOKEN_DECIMALS)
-        )
-        .red()
-    );
-    io::stdout().flush().unwrap();
+    }
 
-    let mut confirm = String::new();
-    io::stdin().read_line(&mut confirm).unwrap();
+    let mut transport_retries = 0u32;
 
-    let confirm = confirm.trim().to_lowercase();
-    if confirm != "y" && !confirm.is_empty() && confirm != "yes" {
-        println!("Claim cancelled.");
-        prompt_to_continue(); // Pause before returning
-        return;
-    }
+    loop {
+        let submit_spinner = start_spinner(args.json, "Submitting claim...");
+        let resp = client
+            .post(format!(
+                "{}://{}/claim?pubkey={}&amount={}",
+                url_prefix,
+                url,
+                key.pubkey().to_string(),
+                claim_amount_grains
+            ))
+            .send()
+            .await;
+        finish_spinner(
+            submit_spinner,
+            resp.is_ok(),
+            if resp.is_ok() { "Claim submitted" } else { "Claim request failed" },
+        );
 
-    println!(
-        "Sending claim request for amount {}...",
-        amount_to_ui_amount(claim_amount_grains, ore_api::consts::TOKEN_DECIMALS)
-    );
-    let resp = client
-        .post(format!(
-            "{}://{}/claim?pubkey={}&amount={}",
-            url_prefix,
-            url,
-            key.pubkey().to_string(),
-            claim_amount_grains
-        ))
-        .send()
-        .await;
+        match resp {
+            Ok(res) => {
+                // Any application response ends this submission. The only path that posts
+                // again is the `--wait` cooldown retry, which starts with a fresh retry budget.
+                transport_retries = 0;
 
-    match resp {
-        Ok(res) => {
-            let response_text = res.text().await.unwrap();
-            if response_text == "SUCCESS" {
-                println!("Successfully claimed rewards!");
-            } else if let Ok(time) = response_text.parse::<u64>() {
-                let time_left = 1800 - time;
-                let secs = time_left % 60;
-                let mins = (time_left / 60) % 60;
-                println!(
-                    "Error: You cannot claim until the time is up. Time left until next claim available: {}m {}s",
-                    mins, secs
-                );
-            } else {
-                println!("Unexpected response: {}", response_text);
+                let response_text = res.text().await.unwrap();
+                match parse_claim_response(&response_text) {
+                    ClaimResponse::Success => {
+                        if !args.json {
+                            println!("Successfully claimed rewards!");
+                        }
+                        let timestamp = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map(|d| d.as_secs())
+                            .unwrap_or(0);
+                        crate::history::record_claim(
+                            timestamp,
+                            &key.pubkey().to_string(),
+                            claim_amount_grains,
+                            &url,
+                            "SUCCESS",
+                        );
+                        finish(
+                            args.json,
+                            non_interactive,
+                            ClaimOutcome {
+                                requested_grains: claim_amount_grains,
+                                available_grains: balance_grains,
+                                status: ClaimStatus::Success,
+                                seconds_until_next_claim: None,
+                            },
+                        );
+                    }
+                    ClaimResponse::Cooldown(time_left) => {
+                        if !args.wait {
+                            if !args.json {
+                                let secs = time_left % 60;
+                                let mins = (time_left / 60) % 60;
+                                println!(
+                                    "Error: You cannot claim until the time is up. Time left until next claim available: {}m {}s",
+                                    mins, secs
+                                );
+                            }
+                            finish(
+                                args.json,
+                                non_interactive,
+                                ClaimOutcome {
+                                    requested_grains: claim_amount_grains,
+                                    available_grains: balance_grains,
+                                    status: ClaimStatus::Cooldown,
+                                    seconds_until_next_claim: Some(time_left),
+                                },
+                            );
+                        }
+
+                        if !args.json {
+                            wait_out_cooldown(time_left).await;
+                        } else {
+                            tokio::time::sleep(Duration::from_secs(time_left + WAIT_RETRY_BUFFER_SECS)).await;
+                        }
+
+                        // Re-check the balance before retrying in case it changed while we waited.
+                        let recheck_spinner = start_spinner(args.json, "Checking rewards...");
+                        let recheck_result = fetch_balance_grains(&client, &url_prefix, &url, &key).await;
+                        finish_spinner(recheck_spinner, recheck_result.is_some(), "Rewards checked");
+
+                        match recheck_result {
+                            Some(current_balance) if current_balance > 0 => {
+                                claim_amount_grains = claim_amount_grains.min(current_balance);
+                            }
+                            _ => {
+                                if !args.json {
+                                    println!("\nThere is no balance left to claim.");
+                                }
+                                finish(
+                                    args.json,
+                                    non_interactive,
+                                    ClaimOutcome {
+                                        requested_grains: claim_amount_grains,
+                                        available_grains: 0,
+                                        status: ClaimStatus::Insufficient,
+                                        seconds_until_next_claim: None,
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    ClaimResponse::Unexpected => {
+                        if !args.json {
+                            println!("Unexpected response: {}", response_text);
+                        }
+                        finish(
+                            args.json,
+                            non_interactive,
+                            ClaimOutcome {
+                                requested_grains: claim_amount_grains,
+                                available_grains: balance_grains,
+                                status: ClaimStatus::Error,
+                                seconds_until_next_claim: None,
+                            },
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                if transport_retries >= args.retry_attempts {
+                    if !args.json {
+                        println!("ERROR: {}", e);
+                        println!(
+                            "Giving up after {} failed attempt(s).",
+                            transport_retries + 1
+                        );
+                    }
+                    finish(
+                        args.json,
+                        non_interactive,
+                        ClaimOutcome {
+                            requested_grains: claim_amount_grains,
+                            available_grains: balance_grains,
+                            status: ClaimStatus::Error,
+                            seconds_until_next_claim: None,
+                        },
+                    );
+                }
+
+                let delay = backoff_delay_secs(args.retry_base_delay, transport_retries);
+                transport_retries += 1;
+
+                if !args.json {
+                    println!("ERROR: {}", e);
+                    println!(
+                        "Retrying in {} seconds... (attempt {}/{})",
+                        delay, transport_retries, args.retry_attempts
+                    );
+                }
+                tokio::time::sleep(Duration::from_secs(delay)).await;
             }
-        }
-        Err(e) => {
-            println!("ERROR: {}", e);
-            println!("Retrying in 5 seconds...");
-            tokio::time::sleep(Duration::from_secs(5)).await;
         }
     }
+}
 
-    prompt_to_continue(); // Pause after the claim operation completes
+/// Sleeps until the on-chain claim cooldown expires, printing a live countdown.
+async fn wait_out_cooldown(time_left_secs: u64) {
+    let total_wait = time_left_secs + WAIT_RETRY_BUFFER_SECS;
+    for remaining in (1..=total_wait).rev() {
+        let secs = remaining % 60;
+        let mins = (remaining / 60) % 60;
+        print!("\rWaiting for claim cooldown to expire: {}m {}s remaining...   ", mins, secs);
+        io::stdout().flush().unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    println!("\rCooldown expired, retrying claim...                          ");
 }
 
 fn prompt_to_continue() {
     println!("\nPress any key to continue...");
     let _ = io::stdin().read(&mut [0u8]).unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_claim_response_success() {
+        assert_eq!(parse_claim_response("SUCCESS"), ClaimResponse::Success);
+    }
+
+    #[test]
+    fn parse_claim_response_cooldown() {
+        assert_eq!(parse_claim_response("900"), ClaimResponse::Cooldown(900));
+    }
+
+    #[test]
+    fn parse_claim_response_cooldown_already_expired() {
+        // time == 1800 means the cooldown has already elapsed, not an out-of-range value.
+        assert_eq!(parse_claim_response("1800"), ClaimResponse::Cooldown(0));
+    }
+
+    #[test]
+    fn parse_claim_response_unexpected_out_of_range() {
+        assert_eq!(parse_claim_response("1801"), ClaimResponse::Unexpected);
+    }
+
+    #[test]
+    fn parse_claim_response_unexpected_garbage() {
+        assert_eq!(parse_claim_response("not a number"), ClaimResponse::Unexpected);
+    }
+
+    #[test]
+    fn backoff_delay_grows_exponentially_until_capped() {
+        assert_eq!(backoff_delay_secs(5, 0), 5);
+        assert_eq!(backoff_delay_secs(5, 1), 10);
+        assert_eq!(backoff_delay_secs(5, 2), 20);
+        assert_eq!(backoff_delay_secs(5, 10), RETRY_MAX_DELAY_SECS);
+    }
+
+    #[test]
+    fn backoff_delay_never_panics_on_large_attempt_counts() {
+        assert_eq!(backoff_delay_secs(5, u32::MAX), RETRY_MAX_DELAY_SECS);
+    }
+}
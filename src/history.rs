@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use spl_token::amount_to_ui_amount;
+
+/// A single completed claim, as recorded to the local ledger.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClaimRecord {
+    pub timestamp: u64,
+    pub pubkey: String,
+    pub requested_grains: u64,
+    pub pool_url: String,
+    pub status: String,
+}
+
+#[derive(Debug, Parser)]
+pub struct HistoryArgs {}
+
+fn ledger_path() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".ore-hq-client").join("claims.jsonl")
+}
+
+/// Appends a completed claim to the local ledger at `~/.ore-hq-client/claims.jsonl`.
+///
+/// Failures to persist the record are logged but never fail the claim itself.
+pub fn record_claim(timestamp: u64, pubkey: &str, requested_grains: u64, pool_url: &str, status: &str) {
+    let path = ledger_path();
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            eprintln!("Warning: could not create claim history directory: {}", e);
+            return;
+        }
+    }
+
+    let record = ClaimRecord {
+        timestamp,
+        pubkey: pubkey.to_string(),
+        requested_grains,
+        pool_url: pool_url.to_string(),
+        status: status.to_string(),
+    };
+
+    let line = match serde_json::to_string(&record) {
+        Ok(line) => line,
+        Err(e) => {
+            eprintln!("Warning: could not serialize claim history record: {}", e);
+            return;
+        }
+    };
+
+    let file = OpenOptions::new().create(true).append(true).open(&path);
+    match file {
+        Ok(mut file) => {
+            if let Err(e) = writeln!(file, "{}", line) {
+                eprintln!("Warning: could not write claim history record: {}", e);
+            }
+        }
+        Err(e) => eprintln!("Warning: could not open claim history file: {}", e),
+    }
+}
+
+fn load_records() -> Vec<ClaimRecord> {
+    let path = ledger_path();
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return Vec::new(),
+    };
+
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<ClaimRecord>(line).ok())
+        .collect()
+}
+
+/// Prints every recorded claim as a table, followed by a running total of ORE claimed per
+/// keypair.
+pub async fn history(_args: HistoryArgs) {
+    let records = load_records();
+
+    if records.is_empty() {
+        println!("No claim history found at {}.", ledger_path().display());
+        return;
+    }
+
+    println!(
+        "{:<20} {:<44} {:<15} {:<30} {}",
+        "TIMESTAMP", "PUBKEY", "AMOUNT (ORE)", "POOL", "STATUS"
+    );
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for record in &records {
+        println!(
+            "{:<20} {:<44} {:<15} {:<30} {}",
+            record.timestamp,
+            record.pubkey,
+            amount_to_ui_amount(record.requested_grains, ore_api::consts::TOKEN_DECIMALS),
+            record.pool_url,
+            record.status,
+        );
+
+        if record.status == "SUCCESS" {
+            *totals.entry(record.pubkey.clone()).or_insert(0) += record.requested_grains;
+        }
+    }
+
+    println!("\nTotal ORE claimed per keypair:");
+    for (pubkey, total_grains) in totals {
+        println!(
+            "  {}: {} ORE",
+            pubkey,
+            amount_to_ui_amount(total_grains, ore_api::consts::TOKEN_DECIMALS)
+        );
+    }
+}